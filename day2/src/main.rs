@@ -1,87 +1,88 @@
 use std::fs;
 use std::env;
-use std::str::FromStr;
-use prime_factorization::Factorization;
+use crate::range::Range;
+use crate::policy::{Policy, Repetition};
 
-struct Range {
-    start: usize,
-    end: usize
-}
-
-impl FromStr for Range {
-    type Err = String;
+mod range;
+mod policy;
 
-    fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let sections: Vec<&str> = line.split("-").collect();
-        Ok(Self {
-            start: sections[0].parse().unwrap(),
-            end: sections[1].parse().unwrap()
-        })
-    }
-}
-
-impl Range {
-    fn find_invalid_ids(&self) -> Vec<usize> {
-        let mut v = vec![];
-        for i in self.start..(self.end+1) {
-            let s = i.to_string();
-            if s.len() < 2 {
-                continue;
-            }
-            if Self::all_same_char(&s) || Self::repeated_patterns(&s) {
-                v.push(i);
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() >= 2 && args.len() <= 4 {
+        let filename = &args[1];
+        let text = fs::read_to_string(filename)
+            .unwrap_or_else(|_| panic!("Error reading from {}", filename));
+        let radix: u32 = match args.get(3).map(|r| r.parse()) {
+            Some(Ok(r)) if (2..=36).contains(&r) => r,
+            Some(_) => {
+                println!("Radix must be between 2 and 36: {}", args[3]);
+                return;
+            },
+            None => 10
+        };
+        let policy: Option<Policy> = match args.get(2).filter(|p| *p != "parts").map(|p| p.parse()) {
+            Some(Ok(p)) => Some(p),
+            Some(Err(e)) => {
+                println!("{}", e);
+                return;
+            },
+            None => None
+        };
+        let mut ranges: Vec<Range> = Vec::new();
+        let mut errors = 0;
+        for (i, s) in text.split(",").enumerate() {
+            match Range::parse_radix(s, radix) {
+                Ok(r) => ranges.push(r),
+                Err(e) => {
+                    println!("Error in #{}: {}", i + 1, e);
+                    errors += 1;
+                }
             }
         }
-        v
-    }
-
-    fn all_same_char(s: &str) -> bool {
-        let c0 = s.chars().next().unwrap();
-        for c in s.chars() {
-            if c != c0 {
-                return false;
-            }
+        if errors > 0 {
+            return;
         }
-        true
-    }
-
-    fn repeated_patterns(s: &str) -> bool {
-        let factorization = Factorization::run(s.len() as u128);
-        for f in factorization.factors {
-            if Self::inner(s, f as usize) {
-                return true;
-            }
+        let (merged, warnings) = range::merge(&ranges);
+        for w in warnings {
+            println!("Warning: {}", w);
+        }
+        println!("Merged {} ranges into {}:", ranges.len(), merged.len());
+        for m in &merged {
+            println!("{}", m);
         }
-        false
+        let ranges: Vec<Range> = merged.into_iter().map(|m| m.range).collect();
+        if let Some(policy) = policy {
+            println!("Answer: {}", total(sum_invalid_ids(&ranges, &policy)));
+        } else {
+            let part1 = sum_invalid_ids(&ranges, &Policy::new(Repetition::Exactly(2)));
+            let part2 = sum_invalid_ids(&ranges, &Policy::new(Repetition::Any));
+            println!("Part1: {}", total(part1));
+            println!("Part2: {}", total(part2));
+        }
+    } else {
+        println!("Please provide 1-3 arguments: Filename, [Policy or \"parts\"], [Radix]");
     }
+}
 
-    fn inner(s: &str, f: usize) -> bool {
-        let l = s.len() / f;
-        let first = &s[0..l];
-        for i in 1..f {
-            if &s[(i*l)..((i+1) * l)] != first {
-                return false;
-            }
+/// None if the sum does not fit in a u128
+fn sum_invalid_ids(ranges: &[Range], policy: &Policy) -> Option<u128> {
+    let mut sum = Some(0_u128);
+    for r in ranges {
+        let count = r.count_invalid_ids(policy);
+        if count <= 10 {
+            let ids: Vec<String> = r.find_invalid_ids(policy).iter().map(|id| id.to_string()).collect();
+            println!("{}: [{}]", r, ids.join(", "));
+        } else {
+            println!("{}: {} invalid IDs", r, count);
         }
-        true
+        sum = sum.zip(r.sum_invalid_ids(policy)).and_then(|(a, b)| a.checked_add(b));
     }
+    sum
 }
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() == 2 {
-        let filename = &args[1];
-        let text = fs::read_to_string(&filename)
-            .expect(&format!("Error reading from {}", filename));
-        let ranges: Vec<Range> = text.split(",").map(|s| s.parse().unwrap()).collect();
-        let mut sum = 0;
-        for r in ranges {
-            for id in r.find_invalid_ids() {
-                sum += id;
-            }
-        }
-        println!("Answer: {}", sum);
-    } else {
-        println!("Please provide 1 argument: Filename");
+fn total(sum: Option<u128>) -> String {
+    match sum {
+        Some(n) => n.to_string(),
+        None => String::from("too large to sum")
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,59 @@
+use std::str::FromStr;
+
+/// How many times a block must repeat for an ID to be invalid
+#[derive(Copy, Clone)]
+pub enum Repetition {
+    Exactly(u32),
+    AtLeast(u32),
+    Any
+}
+
+#[derive(Copy, Clone)]
+pub struct Policy {
+    pub repetition: Repetition,
+    pub min_block_len: u32
+}
+
+impl FromStr for Policy {
+    type Err = String;
+
+    /// `<repeats>[:<min block length>]` where repeats is `N` (exactly), `N+` (at least) or `any`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (repeats, min_block_len) = match s.split_once(':') {
+            Some((r, m)) => (r, m.parse().map_err(|_| format!("Invalid minimum block length: {}", m))?),
+            None => (s, 1)
+        };
+        let repetition = if repeats == "any" {
+            Repetition::Any
+        } else if let Some(k) = repeats.strip_suffix('+') {
+            Repetition::AtLeast(k.parse().map_err(|_| format!("Invalid repeats: {}", repeats))?)
+        } else {
+            Repetition::Exactly(repeats.parse().map_err(|_| format!("Invalid repeats: {}", repeats))?)
+        };
+        Ok(Self {
+            repetition,
+            min_block_len
+        })
+    }
+}
+
+impl Policy {
+    pub fn new(repetition: Repetition) -> Self {
+        Self {
+            repetition,
+            min_block_len: 1
+        }
+    }
+
+    /// Whether an ID made of a `block_len` digit block repeated `repeats` times is invalid
+    pub fn allows(&self, block_len: u32, repeats: u32) -> bool {
+        if block_len < self.min_block_len || repeats < 2 {
+            return false;
+        }
+        match self.repetition {
+            Repetition::Exactly(k) => repeats == k,
+            Repetition::AtLeast(k) => repeats >= k,
+            Repetition::Any => true
+        }
+    }
+}
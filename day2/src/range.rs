@@ -0,0 +1,251 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::policy::Policy;
+
+#[derive(Copy, Clone)]
+pub struct Range {
+    pub start: u128,
+    pub end: u128,
+    /// The base which IDs in this range are written in, and checked for repeats in
+    pub radix: u32
+}
+
+impl FromStr for Range {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        Self::parse_radix(line, 10)
+    }
+}
+
+impl Range {
+    /// Parses a range written in the given radix (2 to 36)
+    pub fn parse_radix(line: &str, radix: u32) -> Result<Self, String> {
+        let line = line.trim();
+        let (start, end) = line.split_once('-').ok_or(format!("Missing '-' in range: {}", line))?;
+        let range = Self {
+            start: u128::from_str_radix(start, radix).map_err(|_| format!("Invalid start of range: {}", line))?,
+            end: u128::from_str_radix(end, radix).map_err(|_| format!("Invalid end of range: {}", line))?,
+            radix
+        };
+        if range.start > range.end {
+            Err(format!("Range starts after it ends: {}", line))
+        } else {
+            Ok(range)
+        }
+    }
+}
+
+impl Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", to_radix(self.start, self.radix), to_radix(self.end, self.radix))
+    }
+}
+
+/// A range covering one or more of the input ranges, identified by their position in the input (starting from 1)
+pub struct MergedRange {
+    pub range: Range,
+    pub sources: Vec<usize>
+}
+
+impl Display for MergedRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sources: Vec<String> = self.sources.iter().map(|i| format!("#{}", i)).collect();
+        write!(f, "{} <- {}", self.range, sources.join(", "))
+    }
+}
+
+/// Sorts the ranges and merges any which overlap or are adjacent, so that no ID is counted twice.
+/// Returns the merged ranges along with a warning for each merge.
+pub fn merge(ranges: &[Range]) -> (Vec<MergedRange>, Vec<String>) {
+    let mut sorted: Vec<(usize, &Range)> = ranges.iter().enumerate().map(|(i, r)| (i + 1, r)).collect();
+    sorted.sort_by_key(|(_, r)| (r.start, r.end));
+    let mut merged: Vec<MergedRange> = Vec::new();
+    let mut warnings = Vec::new();
+    let mut previous: Option<(usize, &Range)> = None;
+    for (i, r) in sorted {
+        if let Some(last) = merged.last_mut() && r.start <= last.range.end.saturating_add(1) {
+            let (p, prev) = previous.unwrap();
+            if prev.start == r.start && prev.end == r.end {
+                warnings.push(format!("#{} {} duplicates #{}", i, r, p));
+            } else if r.start > last.range.end {
+                warnings.push(format!("#{} {} is adjacent to {}", i, r, last.range));
+            } else {
+                warnings.push(format!("#{} {} overlaps {}", i, r, last.range));
+            }
+            last.range.end = last.range.end.max(r.end);
+            last.sources.push(i);
+        } else {
+            merged.push(MergedRange {
+                range: *r,
+                sources: vec![i]
+            });
+        }
+        previous = Some((i, r));
+    }
+    (merged, warnings)
+}
+
+/// An invalid ID, along with the shortest block (allowed by the policy) which repeats to make it
+pub struct InvalidId {
+    pub id: u128,
+    pub block: u128,
+    pub repeats: u32,
+    pub radix: u32
+}
+
+impl Display for InvalidId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({} x{})", to_radix(self.id, self.radix), to_radix(self.block, self.radix), self.repeats)?;
+        if self.radix != 10 {
+            write!(f, " = {}", self.id)?;
+        }
+        Ok(())
+    }
+}
+
+impl Range {
+    /// Every ID in the range which the policy considers invalid (when written in the range's radix), in ascending order.
+    /// IDs are built from their blocks rather than by checking every number in the range,
+    /// and each ID is only produced once (from its shortest allowed block).
+    pub fn find_invalid_ids(&self, policy: &Policy) -> Vec<InvalidId> {
+        let mut v = vec![];
+        for len in self.lengths() {
+            let block_lens = allowed_block_lens(len, policy);
+            for (i, block_len) in block_lens.iter().enumerate() {
+                let multiplier = repeat_multiplier(len, *block_len, self.radix);
+                if let Some((min_block, max_block)) = self.block_bounds(len, *block_len, multiplier) {
+                    for block in min_block..(max_block + 1) {
+                        let period = shortest_period(block, *block_len, self.radix);
+                        if !block_lens[0..i].iter().any(|shorter| shorter.is_multiple_of(period)) {
+                            v.push(InvalidId {
+                                id: block * multiplier,
+                                block,
+                                repeats: len / block_len,
+                                radix: self.radix
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        v.sort_by_key(|i| i.id);
+        v
+    }
+
+    pub fn count_invalid_ids(&self, policy: &Policy) -> u128 {
+        self.total(policy, |min_block, max_block, _| Some(max_block - min_block + 1)).unwrap()
+    }
+
+    /// None if the sum does not fit in a u128
+    pub fn sum_invalid_ids(&self, policy: &Policy) -> Option<u128> {
+        self.total(policy, |min_block, max_block, multiplier| series_sum(min_block, max_block)?.checked_mul(multiplier))
+    }
+
+    /// Adds up `f(min_block, max_block, multiplier)` over the invalid IDs without enumerating them.
+    /// For each length, the IDs whose shortest repeating block has length `p` are all the IDs
+    /// repeating a block of length `p`, less those already counted for the shorter blocks dividing `p`.
+    /// These are then included if `p` divides any block length allowed by the policy.
+    fn total<F: Fn(u128, u128, u128) -> Option<u128>>(&self, policy: &Policy, f: F) -> Option<u128> {
+        let mut total = 0_u128;
+        for len in self.lengths() {
+            let block_lens = allowed_block_lens(len, policy);
+            let periods: Vec<u32> = divisors(len).into_iter().filter(|p| *p != len).collect();
+            let mut exact: Vec<u128> = Vec::new();
+            for period in &periods {
+                let multiplier = repeat_multiplier(len, *period, self.radix);
+                let mut n = match self.block_bounds(len, *period, multiplier) {
+                    Some((min_block, max_block)) => f(min_block, max_block, multiplier)?,
+                    None => 0
+                };
+                for (shorter, e) in periods.iter().zip(&exact) {
+                    if period.is_multiple_of(*shorter) {
+                        n -= e;
+                    }
+                }
+                exact.push(n);
+                if block_lens.iter().any(|b| b.is_multiple_of(*period)) {
+                    total = total.checked_add(n)?;
+                }
+            }
+        }
+        Some(total)
+    }
+
+    /// The digit lengths of the IDs in this range
+    fn lengths(&self) -> std::ops::Range<u32> {
+        if self.start > self.end {
+            0..0
+        } else {
+            digit_count(self.start, self.radix)..(digit_count(self.end, self.radix) + 1)
+        }
+    }
+
+    /// The smallest and largest block of `block_len` digits whose repetition to `len` digits lies within this range
+    fn block_bounds(&self, len: u32, block_len: u32, multiplier: u128) -> Option<(u128, u128)> {
+        let radix = self.radix as u128;
+        let min_id = self.start.max(radix.pow(len - 1));
+        let max_id = self.end.min(radix.checked_pow(len).map(|p| p - 1).unwrap_or(u128::MAX));
+        let min_block = min_id.div_ceil(multiplier).max(radix.pow(block_len - 1));
+        let max_block = (max_id / multiplier).min(radix.pow(block_len) - 1);
+        if min_id > max_id || min_block > max_block {
+            None
+        } else {
+            Some((min_block, max_block))
+        }
+    }
+}
+
+fn digit_count(n: u128, radix: u32) -> u32 {
+    n.checked_ilog(radix as u128).unwrap_or(0) + 1
+}
+
+fn to_radix(mut n: u128, radix: u32) -> String {
+    let mut digits = Vec::new();
+    loop {
+        digits.push(char::from_digit((n % radix as u128) as u32, radix).unwrap());
+        n /= radix as u128;
+        if n == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().collect()
+}
+
+fn divisors(n: u32) -> Vec<u32> {
+    (1..(n + 1)).filter(|d| n.is_multiple_of(*d)).collect()
+}
+
+/// The block lengths, in increasing order, which can repeat to make an invalid `len` digit ID
+fn allowed_block_lens(len: u32, policy: &Policy) -> Vec<u32> {
+    divisors(len).into_iter().filter(|b| policy.allows(*b, len / b)).collect()
+}
+
+/// The number which turns a block of `block_len` digits into that block repeated to `len` digits, eg. 10101 for (6, 2, 10)
+fn repeat_multiplier(len: u32, block_len: u32, radix: u32) -> u128 {
+    let mut m = 0;
+    for _ in 0..(len / block_len) {
+        m = m * (radix as u128).pow(block_len) + 1;
+    }
+    m
+}
+
+/// The length of the shortest block which repeats to make up this `len` digit number
+fn shortest_period(n: u128, len: u32, radix: u32) -> u32 {
+    for p in divisors(len) {
+        if p != len && (n % (radix as u128).pow(p)).checked_mul(repeat_multiplier(len, p, radix)) == Some(n) {
+            return p;
+        }
+    }
+    len
+}
+
+fn series_sum(min: u128, max: u128) -> Option<u128> {
+    let (a, b) = (min + max, max - min + 1);
+    if a % 2 == 0 {
+        (a / 2).checked_mul(b)
+    } else {
+        a.checked_mul(b / 2)
+    }
+}
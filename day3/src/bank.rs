@@ -0,0 +1,235 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use std::collections::VecDeque;
+use num_bigint::BigUint;
+use crate::mode::{Constraint, Goal, Mode};
+
+pub struct Bank {
+    batteries: Vec<usize>,
+    /// The base which battery ratings (and so joltages) are written in
+    radix: u32
+}
+
+/// The batteries switched on in a bank, by index, and the joltage they produce
+pub struct Selection {
+    pub indices: Vec<usize>,
+    pub jolt: BigUint
+}
+
+impl FromStr for Bank {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        Self::parse_radix(line, 10)
+    }
+}
+
+impl Bank {
+    /// Parses a bank of batteries rated in the given radix (2 to 36), eg. 0-9a-f for hex
+    pub fn parse_radix(line: &str, radix: u32) -> Result<Self, String> {
+        let mut batteries = Vec::new();
+        for (i, c) in line.chars().enumerate() {
+            match c.to_digit(radix) {
+                Some(d) => batteries.push(d as usize),
+                None => return Err(format!("Invalid battery rating '{}' at column {}", c, i + 1))
+            }
+        }
+        Ok(Self {
+            batteries,
+            radix
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.batteries.len()
+    }
+
+    /// None if there are fewer than `digits` batteries in the bank
+    pub fn max_jolt(&self, digits: usize) -> Option<Selection> {
+        Self::greedy_jolt(&self.batteries, digits, self.radix)
+    }
+
+    /// None if the bank has too few batteries to choose `digits` of them within the mode's constraint
+    pub fn select(&self, digits: usize, mode: &Mode) -> Option<Selection> {
+        match mode.constraint {
+            Constraint::Unconstrained if mode.goal == Goal::Maximise => self.max_jolt(digits),
+            Constraint::Unconstrained => Self::spaced_jolt(&self.batteries, digits, 1, mode, self.radix),
+            Constraint::Spacing(gap) => Self::spaced_jolt(&self.batteries, digits, gap, mode, self.radix),
+            Constraint::Contiguous => Self::window_jolt(&self.batteries, digits, mode, self.radix)
+        }
+    }
+
+    /// The bank's ratings, with the selected batteries in brackets
+    pub fn highlight(&self, selection: &Selection) -> String {
+        let mut s = String::new();
+        let mut selected = selection.indices.iter().peekable();
+        for (i, b) in self.batteries.iter().enumerate() {
+            if selected.next_if_eq(&&i).is_some() {
+                s.push_str(&format!("[{}]", Self::rating(*b, self.radix)));
+            } else {
+                s.push(Self::rating(*b, self.radix));
+            }
+        }
+        s
+    }
+
+    /// Picks the largest number made from `digits` batteries in a single pass, by keeping a stack of the chosen batteries
+    /// and dropping any which are smaller than the current battery, as long as enough batteries remain to fill the stack.
+    fn greedy_jolt(options: &[usize], digits: usize, radix: u32) -> Option<Selection> {
+        if options.len() < digits {
+            return None;
+        }
+        let mut stack: Vec<usize> = Vec::with_capacity(digits);
+        for (i, b) in options.iter().enumerate() {
+            let remaining = options.len() - i;
+            while let Some(top) = stack.last() && options[*top] < *b && stack.len() - 1 + remaining >= digits {
+                stack.pop();
+            }
+            if stack.len() < digits {
+                stack.push(i);
+            }
+        }
+        Some(Selection {
+            jolt: Self::number(options, &stack, radix),
+            indices: stack
+        })
+    }
+
+    /// Picks each digit in turn as the best battery (earliest if tied) which still leaves room for the rest.
+    /// The range of allowed batteries only ever moves to the right, so the best is tracked with a queue
+    /// which drops any battery that is beaten by a later one.
+    fn spaced_jolt(options: &[usize], digits: usize, gap: usize, mode: &Mode, radix: u32) -> Option<Selection> {
+        if digits == 0 {
+            return Some(Selection { indices: Vec::new(), jolt: BigUint::ZERO });
+        }
+        let span = (digits - 1).checked_mul(gap)?;
+        if span >= options.len() {
+            return None;
+        }
+        let mut indices: Vec<usize> = Vec::with_capacity(digits);
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        let mut next = 0;
+        for d in 0..digits {
+            let min = indices.last().map(|i| i + gap).unwrap_or(0);
+            let max = options.len() - 1 - (digits - 1 - d) * gap;
+            while next <= max {
+                while let Some(back) = queue.back() && mode.prefers(options[next], options[*back]) {
+                    queue.pop_back();
+                }
+                queue.push_back(next);
+                next += 1;
+            }
+            while let Some(front) = queue.front() && *front < min {
+                queue.pop_front();
+            }
+            indices.push(queue.pop_front().unwrap());
+        }
+        Some(Selection {
+            jolt: Self::number(options, &indices, radix),
+            indices
+        })
+    }
+
+    /// Picks the best run of `digits` adjacent batteries (earliest if tied)
+    fn window_jolt(options: &[usize], digits: usize, mode: &Mode, radix: u32) -> Option<Selection> {
+        if options.len() < digits {
+            return None;
+        }
+        let mut best = 0;
+        for start in 1..(options.len() - digits + 1) {
+            let window = &options[start..(start + digits)];
+            let best_window = &options[best..(best + digits)];
+            if let Some((a, b)) = window.iter().zip(best_window).find(|(a, b)| a != b)
+                && mode.prefers(*a, *b) {
+                best = start;
+            }
+        }
+        let indices: Vec<usize> = (best..(best + digits)).collect();
+        Some(Selection {
+            jolt: Self::number(options, &indices, radix),
+            indices
+        })
+    }
+
+    /// The number made by reading the selected batteries as digits, which may be far larger than a usize
+    fn number(options: &[usize], indices: &[usize], radix: u32) -> BigUint {
+        let digits: Vec<u8> = indices.iter().map(|i| options[*i] as u8).collect();
+        BigUint::from_radix_be(&digits, radix).unwrap_or_default()
+    }
+
+    fn rating(battery: usize, radix: u32) -> char {
+        char::from_digit(battery as u32, radix).unwrap()
+    }
+}
+
+impl Display for Selection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {:?}", self.jolt, self.indices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use super::Bank;
+
+    /// The digits of the largest number made by choosing `digits` batteries in order, trying every combination
+    fn brute_force(options: &[usize], digits: usize) -> Option<Vec<usize>> {
+        if digits == 0 {
+            Some(Vec::new())
+        } else if options.len() < digits {
+            None
+        } else {
+            let mut best: Option<Vec<usize>> = None;
+            for (i, b) in options.iter().enumerate() {
+                if let Some(mut rest) = brute_force(&options[(i + 1)..], digits - 1) {
+                    rest.insert(0, *b);
+                    if best.as_ref().is_none_or(|best| rest > *best) {
+                        best = Some(rest);
+                    }
+                }
+            }
+            best
+        }
+    }
+
+    #[test]
+    fn greedy_matches_brute_force() {
+        let mut seed: u64 = 0x2025;
+        let mut next = |max: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % max as u64) as usize
+        };
+        for _ in 0..2000 {
+            let radix = [2, 10, 16, 36][next(4)];
+            let len = 1 + next(14);
+            let distinct = 1 + next(radix as usize); // fewer distinct ratings means more ties
+            let line: String = (0..len).map(|_| char::from_digit(next(distinct) as u32, radix).unwrap()).collect();
+            let bank = Bank::parse_radix(&line, radix).unwrap();
+            let digits = next(len + 2);
+            let expected = brute_force(&bank.batteries, digits);
+            let selection = bank.max_jolt(digits);
+            match (selection, expected) {
+                (Some(selection), Some(expected)) => {
+                    assert!(selection.indices.windows(2).all(|w| w[0] < w[1]), "{} choosing {}: {:?}", line, digits, selection.indices);
+                    let chosen: Vec<usize> = selection.indices.iter().map(|i| bank.batteries[*i]).collect();
+                    assert_eq!(chosen, expected, "{} choosing {}", line, digits);
+                    let expected_jolt = expected.iter().fold(BigUint::ZERO, |n, d| n * radix + *d);
+                    assert_eq!(selection.jolt, expected_jolt, "{} choosing {}", line, digits);
+                },
+                (None, None) => {},
+                (selection, expected) => panic!("{} choosing {}: greedy {:?}, brute force {:?}", line, digits, selection.map(|s| s.indices), expected)
+            }
+        }
+    }
+
+    #[test]
+    fn greedy_keeps_zero_ratings() {
+        let bank: Bank = "500".parse().unwrap();
+        assert_eq!(bank.max_jolt(3).unwrap().jolt, BigUint::from(500_usize));
+        let bank: Bank = "1020".parse().unwrap();
+        assert_eq!(bank.max_jolt(3).unwrap().jolt, BigUint::from(120_usize));
+    }
+}
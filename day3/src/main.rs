@@ -1,81 +1,106 @@
 use std::fs;
 use std::env;
-use std::str::FromStr;
+use num_bigint::BigUint;
+use crate::bank::Bank;
+use crate::mode::Mode;
 
-struct Bank {
-    batteries: Vec<usize>
-}
+mod bank;
+mod mode;
 
-impl FromStr for Bank {
-    type Err = String;
+/// Reads one bank per line, reporting any invalid battery ratings by line and column
+fn read_banks(filename: &str, radix: u32) -> Option<Vec<Bank>> {
+    let text = fs::read_to_string(filename)
+        .unwrap_or_else(|_| panic!("Error reading from {}", filename));
+    let mut banks = Vec::new();
+    let mut valid = true;
+    for (i, line) in text.lines().enumerate() {
+        match Bank::parse_radix(line, radix) {
+            Ok(b) => banks.push(b),
+            Err(e) => {
+                println!("Line {}: {}", i + 1, e);
+                valid = false;
+            }
+        }
+    }
+    if valid {
+        Some(banks)
+    } else {
+        None
+    }
+}
 
-    fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let batteries: Vec<usize> = line.chars().map(|c| c.to_string().parse().unwrap()).collect();
-        Ok(Self {
-            batteries
-        })
+fn parse_radix(arg: Option<&String>) -> Result<u32, String> {
+    match arg.map(|r| r.parse()) {
+        Some(Ok(r)) if (2..=36).contains(&r) => Ok(r),
+        Some(_) => Err(format!("Radix must be between 2 and 36: {}", arg.unwrap())),
+        None => Ok(10)
     }
 }
 
-impl Bank {
-    fn max_jolt(&self, digits: usize) -> usize {
-        // let first = &self.batteries[0..(self.batteries.len()-1)];
-        // let f_max = first.iter().max().unwrap();
-        // let mut max_s = 0;
-        // for (i, f) in first.iter().enumerate().filter(|(_, f)| *f == f_max) {
-        //     let second = &self.batteries[(i+1)..self.batteries.len()];
-        //     let s = second.iter().max().unwrap();
-        //     if *s > max_s {
-        //         max_s = *s;
-        //     }
-        // }
-        // f_max * 10 + max_s
-        Self::jolt(&self.batteries, digits).unwrap()
+fn parse_mode(arg: Option<&String>) -> Result<Mode, String> {
+    arg.map(|m| m.parse()).unwrap_or(Ok(Mode::default()))
+}
+
+fn parse_digits(d: &str) -> Result<usize, String> {
+    d.parse().map_err(|_| format!("Invalid digit count: {}", d))
+}
+
+fn report(digits: usize, label: &str, banks: &[Bank], mode: &Mode) {
+    match total_jolt(banks, digits, mode) {
+        Some(sum) => println!("{}: {}", label, sum),
+        None => println!("{}: not enough batteries in every bank", label)
     }
+}
 
-    fn jolt(options: &[usize], digits: usize) -> Option<usize> {
-        if options.len() < digits {
-            None
-        } else if digits == 1 {
-            Some(*options.iter().max().unwrap())
-        } else {
-            let mut max = *options.iter().max().unwrap();
-            let mut max_inner = None;
-            while max > 0 {
-                for (i, _) in options.iter().enumerate().filter(|(_, v)| **v == max) {
-                    if let Some(inner) = Self::jolt(&options[(i+1)..], digits - 1) {
-                        if max_inner.is_none() || inner > max_inner.unwrap() {
-                            max_inner = Some(inner);
-                        }
-                    }
-                }
-                if let Some(valid_max_inner) = max_inner {
-                    return Some(max * 10_usize.pow((digits - 1).try_into().unwrap()) + valid_max_inner);
-                } else {
-                    max -= 1;
-                }
-            }
-            None
-        }    
+/// Sums the joltage selected from every bank, or None if any bank has too few batteries to choose `digits` of them
+fn total_jolt(banks: &[Bank], digits: usize, mode: &Mode) -> Option<BigUint> {
+    let mut sum = BigUint::ZERO;
+    for b in banks {
+        sum += b.select(digits, mode)?.jolt;
     }
+    Some(sum)
 }
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    if args.len() == 2 {
-        let filename = &args[1];
-        let text = fs::read_to_string(&filename)
-            .expect(&format!("Error reading from {}", filename));
-        let banks: Vec<Bank> = text.lines().map(|s| s.parse().unwrap()).collect();
-        let mut s1 = 0;
-        let mut s2 = 0;
-        for b in banks {
-            s1 += b.max_jolt(2);
-            s2 += b.max_jolt(12);
+    if args.len() >= 4 && args.len() <= 6 && args[1] == "show" {
+        let (digits, radix, mode) = match (parse_digits(&args[3]), parse_radix(args.get(4)), parse_mode(args.get(5))) {
+            (Ok(d), Ok(r), Ok(m)) => (d, r, m),
+            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+                println!("{}", e);
+                return;
+            }
+        };
+        if let Some(banks) = read_banks(&args[2], radix) {
+            for b in banks {
+                match b.select(digits, &mode) {
+                    Some(selection) => println!("{} = {}", b.highlight(&selection), selection),
+                    None => println!("Only {} batteries, cannot choose {}", b.len(), digits)
+                }
+            }
+        }
+    } else if args.len() >= 2 && args.len() <= 5 && args[1] != "show" {
+        let list = args.get(2).map(|l| l.split(',').map(parse_digits).collect::<Result<Vec<usize>, String>>()).transpose();
+        let (list, radix, mode) = match (list, parse_radix(args.get(3)), parse_mode(args.get(4))) {
+            (Ok(l), Ok(r), Ok(m)) => (l, r, m),
+            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+                println!("{}", e);
+                return;
+            }
+        };
+        if let Some(banks) = read_banks(&args[1], radix) {
+            if let Some(list) = list {
+                for digits in list {
+                    report(digits, &format!("{} digits", digits), &banks, &mode);
+                }
+            } else {
+                report(2, "Part1", &banks, &mode);
+                report(12, "Part2", &banks, &mode);
+            }
         }
-        println!("Part1: {}", s1);
-        println!("Part2: {}", s2);
     } else {
-        println!("Please provide 1 argument: Filename");
+        println!("Please provide 1-4 arguments: Filename, [Comma separated digit counts], [Radix], [Mode]");
+        println!("Or \"show\", Filename, Digits, [Radix], [Mode] to highlight the selected batteries");
+        println!("Where Mode is \"max\" or \"min\", optionally followed by \",window\" or \",spacing:N\"");
     }
 }
\ No newline at end of file
@@ -0,0 +1,65 @@
+use std::str::FromStr;
+
+#[derive(Copy, Clone, PartialEq, Default)]
+pub enum Goal {
+    #[default]
+    Maximise,
+    Minimise
+}
+
+#[derive(Copy, Clone, PartialEq, Default)]
+pub enum Constraint {
+    /// Any batteries may be chosen, as long as they stay in order
+    #[default]
+    Unconstrained,
+    /// Chosen batteries must be at least this many positions apart (1 being adjacent)
+    Spacing(usize),
+    /// Chosen batteries must all be next to each other
+    Contiguous
+}
+
+/// How batteries are chosen from a bank, eg. `max`, `min,window` or `max,spacing:3`
+#[derive(Copy, Clone, Default)]
+pub struct Mode {
+    pub goal: Goal,
+    pub constraint: Constraint
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (goal, constraint) = s.split_once(',').unwrap_or((s, ""));
+        let goal = match goal {
+            "max" => Goal::Maximise,
+            "min" => Goal::Minimise,
+            _ => return Err(format!("Invalid goal: {}", goal))
+        };
+        let constraint = if constraint.is_empty() {
+            Constraint::Unconstrained
+        } else if constraint == "window" {
+            Constraint::Contiguous
+        } else if let Some(gap) = constraint.strip_prefix("spacing:") {
+            match gap.parse() {
+                Ok(g) if g > 0 => Constraint::Spacing(g),
+                _ => return Err(format!("Invalid spacing: {}", gap))
+            }
+        } else {
+            return Err(format!("Invalid constraint: {}", constraint));
+        };
+        Ok(Self {
+            goal,
+            constraint
+        })
+    }
+}
+
+impl Mode {
+    /// Whether rating `a` should be chosen over rating `b`
+    pub fn prefers(&self, a: usize, b: usize) -> bool {
+        match self.goal {
+            Goal::Maximise => a > b,
+            Goal::Minimise => a < b
+        }
+    }
+}
@@ -1,98 +1,83 @@
 use std::fs;
 use std::env;
-use std::str::FromStr;
-use std::collections::HashSet;
+use crate::map::Map;
+use crate::rules::Rules;
 
-#[derive(Hash, Eq, PartialEq, Copy, Clone)]
-struct Pos {
-    x: usize,
-    y: usize
-}
-
-struct Map {
-    rolls: HashSet<Pos>,
-    _size: Pos
-}
+mod map;
+mod rules;
+mod removal;
 
-impl FromStr for Map {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut y = 0;
-        let mut x = 0;
-        let mut rolls = HashSet::new();
-        for line in s.lines() {
-            x = 0;
-            for ch in line.chars() {
-                if ch == '@' {
-                    rolls.insert(Pos { x, y });
-                }
-                x += 1;
-            }
-            y += 1;
-        }
-        Ok(Self {
-            rolls,
-            _size: Pos { x, y }
-        })
+fn parse_rules(args: &[String]) -> Result<Rules, String> {
+    let mut rules = Rules::default();
+    if let Some(neighbourhood) = args.first() {
+        rules.neighbourhood = neighbourhood.parse()?;
     }
-}
-
-impl Pos {
-    fn adjacents(&self) -> Vec<Pos> {
-        let mut v = vec![
-            Pos { x: self.x + 1, y: self.y },
-            Pos { x: self.x , y: self.y + 1 },
-            Pos { x: self.x + 1, y: self.y + 1 },
-        ];
-        if self.x > 0 {
-            v.push(Pos { x: self.x - 1, y: self.y });
-            v.push(Pos { x: self.x - 1, y: self.y + 1 });
-            if self.y > 0 {
-                v.push(Pos { x: self.x - 1, y: self.y - 1 });
-            }
-        }
-        if self.y > 0 {
-            v.push(Pos { x: self.x , y: self.y - 1 });
-            v.push(Pos { x: self.x + 1, y: self.y - 1 });
-        }
-        v
+    if let Some(threshold) = args.get(1) {
+        rules.threshold = threshold.parse().map_err(|_| format!("Invalid threshold: {}", threshold))?;
     }
-}
-
-impl Map {
-    fn moveable_rolls(&self) -> Vec<Pos> {
-        let mut v = Vec::new();
-        for pos in &self.rolls {
-            if pos.adjacents().into_iter().filter(|p| self.rolls.contains(p)).count() < 4 {
-                v.push(*pos);
-            }
+    if let Some(wrap) = args.get(2) {
+        if wrap != "wrap" {
+            return Err(format!("Expected \"wrap\": {}", wrap));
         }
-        v
+        rules.wrap = true;
     }
+    Ok(rules)
 }
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    if args.len() == 2 {
-        let filename = &args[1];
-        let text = fs::read_to_string(&filename)
-            .expect(&format!("Error reading from {}", filename));
+    let (output, rest) = match args.get(1).map(|a| a.as_str()) {
+        Some("layers") | Some("csv") => (Some(args[1].as_str()), &args[2..]),
+        Some("frames") if args.len() > 2 => (Some(args[1].as_str()), &args[3..]),
+        _ => (None, &args[1..])
+    };
+    if !rest.is_empty() && rest.len() <= 4 {
+        let filename = &rest[0];
+        let text = fs::read_to_string(filename)
+            .unwrap_or_else(|_| panic!("Error reading from {}", filename));
         let mut map: Map = text.parse().unwrap();
-        let mut removed = 0;
-        loop {
-            let moveable = map.moveable_rolls();
-            if moveable.len() == 0 {
-                break;
+        let rules = match parse_rules(&rest[1..]) {
+            Ok(r) => r,
+            Err(e) => {
+                println!("{}", e);
+                return;
             }
-            println!("Removing {} rolls", moveable.len());
-            removed += moveable.len();
-            for roll in moveable {
-                map.rolls.remove(&roll);                
+        };
+        let removal = map.remove_all(&rules);
+        match output {
+            Some("layers") => print!("{}", removal.render()),
+            Some("csv") => print!("{}", removal.csv()),
+            Some("frames") => {
+                let dir = &args[2];
+                fs::create_dir_all(dir).unwrap();
+                let scale = (512 / ((removal.size.x + 1) * removal.size.z).max(removal.size.y).max(1)).max(1);
+                // one frame per round, and a final frame of the core
+                for round in 1..(removal.rounds.len() + 2) {
+                    let path = format!("{}/frame{:04}.ppm", dir, round);
+                    fs::write(&path, removal.frame(round, scale))
+                        .unwrap_or_else(|_| panic!("Error writing to {}", path));
+                }
+                println!("Wrote {} frames to {}", removal.rounds.len() + 1, dir);
+            },
+            _ => {
+                for (i, count) in removal.rounds.iter().enumerate() {
+                    println!("Round {}: removed {} rolls", i + 1, count);
+                }
+                let components = map.components(&rules);
+                let core: usize = components.iter().sum();
+                println!("Core: {} rolls in {} components", core, components.len());
+                if !components.is_empty() {
+                    println!("Component sizes: largest {}, smallest {}, mean {:.1}",
+                        components[0], components[components.len() - 1], core as f64 / components.len() as f64);
+                }
+                println!("Part1: {}", removal.accessible());
+                println!("Part2: {}", removal.removable());
             }
         }
-        println!("Removed: {}", removed);
     } else {
-        println!("Please provide 1 argument: Filename");
+        println!("Please provide 1-4 arguments: Filename, [Neighbourhood], [Threshold], [\"wrap\"]");
+        println!("Where Neighbourhood is \"von-neumann\", \"moore\", \"radius:R\", \"6\", \"18\", \"26\" or \"custom:DX,DY[,DZ];...\"");
+        println!("Optionally preceded by \"layers\" or \"csv\" to show the round each roll was removed in,");
+        println!("or \"frames\", Directory to save an image of each round");
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,158 @@
+use std::str::FromStr;
+use std::collections::VecDeque;
+use crate::rules::Rules;
+use crate::removal::Removal;
+
+#[derive(Hash, Eq, PartialEq, Copy, Clone)]
+pub struct Pos {
+    pub x: usize,
+    pub y: usize,
+    pub z: usize
+}
+
+/// A dense grid of which positions hold a roll, stored row by row then layer by layer
+pub struct Map {
+    rolls: Vec<bool>,
+    size: Pos
+}
+
+impl FromStr for Map {
+    type Err = String;
+
+    /// Layers of a 3D map are separated by blank lines
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut layers: Vec<Vec<&str>> = vec![Vec::new()];
+        for line in s.lines() {
+            if !line.trim().is_empty() {
+                layers.last_mut().unwrap().push(line);
+            } else if !layers.last().unwrap().is_empty() {
+                layers.push(Vec::new());
+            }
+        }
+        if layers.len() > 1 && layers.last().unwrap().is_empty() {
+            layers.pop();
+        }
+        let width = s.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+        let height = layers.iter().map(|layer| layer.len()).max().unwrap_or(0);
+        let depth = layers.len();
+        let mut rolls = vec![false; width * height * depth];
+        for (z, layer) in layers.iter().enumerate() {
+            for (y, line) in layer.iter().enumerate() {
+                for (x, ch) in line.chars().enumerate() {
+                    if ch == '@' {
+                        rolls[(z * height + y) * width + x] = true;
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            rolls,
+            size: Pos { x: width, y: height, z: depth }
+        })
+    }
+}
+
+impl Map {
+    /// Repeatedly removes every moveable roll at once, until none are left.
+    /// Each roll keeps a count of its neighbouring rolls, and only the neighbours of removed rolls
+    /// are checked for the next round, so every roll is looked at a constant number of times.
+    pub fn remove_all(&mut self, rules: &Rules) -> Removal {
+        let offsets = rules.neighbourhood.offsets();
+        // the rolls which count a removed roll as a neighbour are found by looking the opposite way, for stencils which aren't symmetric
+        let reversed: Vec<(isize, isize, isize)> = offsets.iter().map(|(dx, dy, dz)| (-dx, -dy, -dz)).collect();
+        let mut removal = Removal::new(&self.rolls, self.size);
+        let mut counts = vec![0_u32; self.rolls.len()];
+        let mut round = Vec::new();
+        for (i, count) in counts.iter_mut().enumerate() {
+            if self.rolls[i] {
+                *count = self.neighbours(i, &offsets, rules.wrap).filter(|n| self.rolls[*n]).count() as u32;
+                if (*count as usize) < rules.threshold {
+                    round.push(i);
+                }
+            }
+        }
+        // rolls are taken off the map as soon as they are queued for removal, so they are never queued twice
+        for i in &round {
+            self.rolls[*i] = false;
+            removal.remove(*i, 1);
+        }
+        while !round.is_empty() {
+            removal.rounds.push(round.len());
+            let mut next = Vec::new();
+            for i in round {
+                for n in self.neighbours(i, &reversed, rules.wrap) {
+                    // only rolls still on the map have a count to update, empty positions never counted anything
+                    if self.rolls[n] {
+                        counts[n] -= 1;
+                        if (counts[n] as usize) < rules.threshold {
+                            self.rolls[n] = false;
+                            removal.remove(n, removal.rounds.len() + 1);
+                            next.push(n);
+                        }
+                    }
+                }
+            }
+            round = next;
+        }
+        removal
+    }
+
+    /// The sizes of each group of connected rolls still on the map, largest first.
+    /// Rolls are connected if either is in the other's neighbourhood.
+    pub fn components(&self, rules: &Rules) -> Vec<usize> {
+        let mut offsets = rules.neighbourhood.offsets();
+        offsets.extend(rules.neighbourhood.offsets().iter().map(|(dx, dy, dz)| (-dx, -dy, -dz)));
+        let mut seen = vec![false; self.rolls.len()];
+        let mut sizes = Vec::new();
+        for start in 0..self.rolls.len() {
+            if !self.rolls[start] || seen[start] {
+                continue;
+            }
+            seen[start] = true;
+            let mut size = 0;
+            let mut queue = VecDeque::from([start]);
+            while let Some(i) = queue.pop_front() {
+                size += 1;
+                for n in self.neighbours(i, &offsets, rules.wrap) {
+                    if self.rolls[n] && !seen[n] {
+                        seen[n] = true;
+                        queue.push_back(n);
+                    }
+                }
+            }
+            sizes.push(size);
+        }
+        sizes.sort_by(|a, b| b.cmp(a));
+        sizes
+    }
+
+    /// The indices of the positions at the given offsets from an index, which are within the map (or wrapped back onto it)
+    fn neighbours<'a>(&self, i: usize, offsets: &'a [(isize, isize, isize)], wrap: bool) -> impl Iterator<Item = usize> + use<'a> {
+        let (width, height, depth) = (self.size.x as isize, self.size.y as isize, self.size.z as isize);
+        let (x, y, z) = ((i % self.size.x) as isize, (i / self.size.x % self.size.y) as isize, (i / self.size.x / self.size.y) as isize);
+        offsets.iter()
+            .map(move |(dx, dy, dz)| if wrap {
+                ((x + dx).rem_euclid(width), (y + dy).rem_euclid(height), (z + dz).rem_euclid(depth))
+            } else {
+                (x + dx, y + dy, z + dz)
+            })
+            .filter(move |(nx, ny, nz)| *nx >= 0 && *nx < width && *ny >= 0 && *ny < height && *nz >= 0 && *nz < depth)
+            .map(move |(nx, ny, nz)| ((nz * height + ny) * width + nx) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Map;
+    use crate::rules::Rules;
+
+    #[test]
+    fn removes_sample_rolls() {
+        let mut map: Map = include_str!("../test.txt").parse().unwrap();
+        let removal = map.remove_all(&Rules::default());
+        assert_eq!(removal.rounds[0], 13);
+        assert_eq!(removal.rounds.iter().sum::<usize>(), 43);
+        assert_eq!(removal.accessible(), 13);
+        assert_eq!(removal.removable(), 43);
+    }
+}
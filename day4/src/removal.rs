@@ -0,0 +1,121 @@
+use crate::map::Pos;
+
+/// What happened to a position on the map
+#[derive(Copy, Clone, PartialEq)]
+pub enum Layer {
+    /// There was never a roll here
+    Empty,
+    /// The roll here was removed in this round (starting from 1)
+    Removed(u32),
+    /// The roll here could never be removed
+    Core
+}
+
+/// The number of rolls removed in each round until no more rolls can be removed, and when each roll was removed
+pub struct Removal {
+    pub rounds: Vec<usize>,
+    /// The round each position was removed in, 0 if it was empty, or `CORE` if it was never removed
+    layers: Vec<u32>,
+    pub size: Pos
+}
+
+const CORE: u32 = u32::MAX;
+
+impl Removal {
+    /// Starts with every roll in the core, until it is marked as removed
+    pub fn new(rolls: &[bool], size: Pos) -> Self {
+        Self {
+            rounds: Vec::new(),
+            layers: rolls.iter().map(|r| if *r { CORE } else { 0 }).collect(),
+            size
+        }
+    }
+
+    pub fn remove(&mut self, i: usize, round: usize) {
+        self.layers[i] = round as u32;
+    }
+
+    /// Rolls which can be removed straight away
+    pub fn accessible(&self) -> usize {
+        self.rounds.first().copied().unwrap_or(0)
+    }
+
+    /// Rolls which can be removed once the rolls around them have been removed
+    pub fn removable(&self) -> usize {
+        self.rounds.iter().sum()
+    }
+
+    pub fn layer(&self, pos: Pos) -> Layer {
+        match self.layers[(pos.z * self.size.y + pos.y) * self.size.x + pos.x] {
+            0 => Layer::Empty,
+            CORE => Layer::Core,
+            round => Layer::Removed(round)
+        }
+    }
+
+    /// The map with each roll shown as the round it was removed in (1-9 then a-z, or + after that),
+    /// or `@` if it was never removed, with layers separated by blank lines
+    pub fn render(&self) -> String {
+        let mut s = String::with_capacity((self.size.x + 1) * (self.size.y + 1) * self.size.z);
+        for z in 0..self.size.z {
+            if z > 0 {
+                s.push('\n');
+            }
+            for y in 0..self.size.y {
+                for x in 0..self.size.x {
+                    s.push(match self.layer(Pos { x, y, z }) {
+                        Layer::Empty => '.',
+                        Layer::Core => '@',
+                        Layer::Removed(round) => char::from_digit(round, 36).unwrap_or('+')
+                    });
+                }
+                s.push('\n');
+            }
+        }
+        s
+    }
+
+    /// One line for each roll, with the round it was removed in or `never`
+    pub fn csv(&self) -> String {
+        let mut s = String::from("x,y,z,round\n");
+        for z in 0..self.size.z {
+            for y in 0..self.size.y {
+                for x in 0..self.size.x {
+                    match self.layer(Pos { x, y, z }) {
+                        Layer::Empty => {},
+                        Layer::Core => s.push_str(&format!("{},{},{},never\n", x, y, z)),
+                        Layer::Removed(round) => s.push_str(&format!("{},{},{},{}\n", x, y, z, round))
+                    }
+                }
+            }
+        }
+        s
+    }
+
+    /// A PPM image of the map just before the given round, with the rolls removed in that round in red,
+    /// rolls still to be removed in grey and rolls removed in earlier rounds faded out.
+    /// Each position is drawn as a square of `scale` pixels, with the layers of a 3D map side by side.
+    pub fn frame(&self, round: usize, scale: usize) -> Vec<u8> {
+        let (width, height) = (((self.size.x + 1) * self.size.z - 1) * scale, self.size.y * scale);
+        let mut ppm = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+        ppm.reserve(width * height * 3);
+        for py in 0..height {
+            for px in 0..width {
+                let (x, z) = (px / scale % (self.size.x + 1), px / scale / (self.size.x + 1));
+                if x == self.size.x {
+                    // gap between layers
+                    ppm.extend_from_slice(&[0, 0, 128]);
+                    continue;
+                }
+                let colour: [u8; 3] = match self.layer(Pos { x, y: py / scale, z }) {
+                    Layer::Empty => [255, 255, 255],
+                    Layer::Removed(r) if (r as usize) < round => [220, 220, 220],
+                    Layer::Removed(r) if (r as usize) == round => [220, 0, 0],
+                    _ => [64, 64, 64]
+                };
+                ppm.extend_from_slice(&colour);
+            }
+        }
+        ppm
+    }
+}
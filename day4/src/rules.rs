@@ -0,0 +1,106 @@
+use std::str::FromStr;
+
+/// Which positions around a roll count as its neighbours
+pub enum Neighbourhood {
+    /// The 4 orthogonally adjacent positions within a layer
+    VonNeumann,
+    /// The 8 orthogonally or diagonally adjacent positions within a layer
+    Moore,
+    /// Every position within a square of the given radius, within a layer
+    Radius(usize),
+    /// The 6 positions sharing a face in 3D
+    Faces,
+    /// The 18 positions sharing a face or an edge in 3D
+    Edges,
+    /// The 26 positions sharing a face, an edge or a corner in 3D
+    Corners,
+    /// An explicit list of offsets
+    Custom(Vec<(isize, isize, isize)>)
+}
+
+impl FromStr for Neighbourhood {
+    type Err = String;
+
+    /// `von-neumann`, `moore`, `radius:R`, `6`, `18`, `26` or `custom:DX,DY[,DZ];DX,DY[,DZ];...`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "von-neumann" {
+            Ok(Self::VonNeumann)
+        } else if s == "moore" {
+            Ok(Self::Moore)
+        } else if s == "6" {
+            Ok(Self::Faces)
+        } else if s == "18" {
+            Ok(Self::Edges)
+        } else if s == "26" {
+            Ok(Self::Corners)
+        } else if let Some(r) = s.strip_prefix("radius:") {
+            Ok(Self::Radius(r.parse().map_err(|_| format!("Invalid radius: {}", r))?))
+        } else if let Some(list) = s.strip_prefix("custom:") {
+            let mut offsets = Vec::new();
+            for offset in list.split(';') {
+                let mut d: Vec<isize> = Vec::new();
+                for n in offset.split(',') {
+                    d.push(n.trim().parse().map_err(|_| format!("Invalid offset: {}", offset))?);
+                }
+                match d[..] {
+                    [dx, dy] => offsets.push((dx, dy, 0)),
+                    [dx, dy, dz] => offsets.push((dx, dy, dz)),
+                    _ => return Err(format!("Invalid offset: {}", offset))
+                }
+            }
+            Ok(Self::Custom(offsets))
+        } else {
+            Err(format!("Invalid neighbourhood: {}", s))
+        }
+    }
+}
+
+impl Neighbourhood {
+    pub fn offsets(&self) -> Vec<(isize, isize, isize)> {
+        match self {
+            Self::VonNeumann => vec![(0, -1, 0), (-1, 0, 0), (1, 0, 0), (0, 1, 0)],
+            Self::Moore => Self::Radius(1).offsets(),
+            Self::Radius(r) => Self::cube(*r as isize, 0, |_| true),
+            Self::Faces => Self::cube(1, 1, |d| d == 1),
+            Self::Edges => Self::cube(1, 1, |d| d <= 2),
+            Self::Corners => Self::cube(1, 1, |_| true),
+            Self::Custom(offsets) => offsets.clone()
+        }
+    }
+
+    /// Every offset except the origin within `r` across and `depth` layers up or down,
+    /// which moves along a number of axes matching the filter
+    fn cube(r: isize, depth: isize, filter: fn(usize) -> bool) -> Vec<(isize, isize, isize)> {
+        let mut v = Vec::new();
+        for dz in -depth..(depth + 1) {
+            for dy in -r..(r + 1) {
+                for dx in -r..(r + 1) {
+                    let axes = [dx, dy, dz].iter().filter(|d| **d != 0).count();
+                    if axes != 0 && filter(axes) {
+                        v.push((dx, dy, dz));
+                    }
+                }
+            }
+        }
+        v
+    }
+}
+
+/// When a roll can be removed
+pub struct Rules {
+    pub neighbourhood: Neighbourhood,
+    /// A roll can be removed when fewer than this many of its neighbours are rolls
+    pub threshold: usize,
+    /// Whether the edges of the map wrap around to the opposite side
+    pub wrap: bool
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            neighbourhood: Neighbourhood::Moore,
+            threshold: 4,
+            wrap: false
+        }
+    }
+}
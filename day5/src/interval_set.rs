@@ -0,0 +1,120 @@
+use crate::range::{Id, Range};
+
+/// A set of numbers, stored as sorted ranges which don't overlap or touch
+pub struct IntervalSet<T: Id> {
+    ranges: Vec<Range<T>>
+}
+
+impl<T: Id> IntervalSet<T> {
+    /// Sorts the ranges, merging any which overlap or are adjacent
+    pub fn new(ranges: &[Range<T>]) -> Self {
+        let mut sorted: Vec<Range<T>> = ranges.to_vec();
+        sorted.sort_by_key(|r| r.start);
+        let mut merged: Vec<Range<T>> = Vec::new();
+        for r in sorted {
+            if let Some(last) = merged.last_mut() && r.start <= last.end.saturating_add(T::one()) {
+                last.end = last.end.max(r.end);
+            } else {
+                merged.push(r);
+            }
+        }
+        Self {
+            ranges: merged
+        }
+    }
+
+    /// Checks many numbers at once, by sorting them and then binary searching for each one
+    /// only among the ranges after the one found for the previous number
+    pub fn contains_all(&self, numbers: &[T]) -> Vec<bool> {
+        let mut order: Vec<usize> = (0..numbers.len()).collect();
+        order.sort_by_key(|i| numbers[*i]);
+        let mut result = vec![false; numbers.len()];
+        let mut r = 0;
+        for i in order {
+            r += self.ranges[r..].partition_point(|range| range.end < numbers[i]);
+            if r == self.ranges.len() {
+                break;
+            }
+            result[i] = self.ranges[r].contains(numbers[i]);
+        }
+        result
+    }
+
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    /// The total count of numbers in the set, or None if that doesn't fit in a `T`
+    pub fn len(&self) -> Option<T> {
+        let mut total = T::zero();
+        for r in &self.ranges {
+            total = total.checked_add(&r.len()?)?;
+        }
+        Some(total)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut ranges = self.ranges.clone();
+        ranges.extend_from_slice(&other.ranges);
+        Self::new(&ranges)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start <= end {
+                ranges.push(Range { start, end });
+            }
+            // whichever range ends first can't overlap anything else
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self {
+            ranges
+        }
+    }
+
+    /// The numbers within `bounds` which are not in the set
+    pub fn complement(&self, bounds: Range<T>) -> Self {
+        let mut ranges = Vec::new();
+        let mut next = Some(bounds.start);
+        for r in &self.ranges {
+            if r.end < bounds.start {
+                continue;
+            }
+            if r.start > bounds.end {
+                break;
+            }
+            if let Some(n) = next && r.start > n {
+                ranges.push(Range { start: n, end: r.start - T::one() });
+            }
+            next = r.end.checked_add(&T::one());
+        }
+        if let Some(n) = next && n <= bounds.end {
+            ranges.push(Range { start: n, end: bounds.end });
+        }
+        Self {
+            ranges
+        }
+    }
+
+    /// The numbers in this set which are not in the other
+    pub fn difference(&self, other: &Self) -> Self {
+        match (self.ranges.first(), self.ranges.last()) {
+            (Some(first), Some(last)) => self.intersection(&other.complement(Range { start: first.start, end: last.end })),
+            _ => Self { ranges: Vec::new() }
+        }
+    }
+
+    /// The numbers in exactly one of the sets
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.difference(other).union(&other.difference(self))
+    }
+}
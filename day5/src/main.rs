@@ -1,75 +1,95 @@
 use std::fs;
 use std::env;
-use std::str::FromStr;
+use std::sync::Arc;
+use crate::range::{Id, Range};
+use crate::interval_set::IntervalSet;
+use crate::range_index::RangeIndex;
+use crate::server::Server;
 
-struct Range {
-    start: usize,
-    end: usize
-}
-
-impl FromStr for Range {
-    type Err = String;
+mod range;
+mod interval_set;
+mod range_index;
+mod server;
 
-    fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let sections: Vec<&str> = line.split("-").collect();
-        Ok(Self {
-            start: sections[0].parse().unwrap(),
-            end: sections[1].parse().unwrap()
-        })
-    }
+/// The fresh ranges and the ingredients, which are separated by a blank line
+fn read_inventory<T: Id>(filename: &str) -> (Vec<Range<T>>, Vec<T>) {
+    let text = fs::read_to_string(filename)
+        .unwrap_or_else(|_| panic!("Error reading from {}", filename));
+    let lines: Vec<&str> = text.lines().collect();
+    let blank = lines.iter().position(|l| l.trim().is_empty()).unwrap_or(lines.len());
+    let ranges: Vec<Range<T>> = lines[..blank].iter().enumerate()
+        .map(|(i, s)| s.parse().unwrap_or_else(|e| panic!("Line {}: {}", i + 1, e)))
+        .collect();
+    let ingredients: Vec<T> = lines[blank..].iter().enumerate()
+        .filter(|(_, s)| !s.trim().is_empty())
+        .map(|(i, s)| s.trim().parse().unwrap_or_else(|_| panic!("Line {}: Invalid ingredient: {}", blank + i + 1, s)))
+        .collect();
+    (ranges, ingredients)
 }
 
-impl Range {
-    fn contains(&self, n: usize) -> bool {
-        n >= self.start && n <= self.end
-    }
-
-    fn len(&self) -> usize {
-        self.end - self.start + 1
+fn total<T: Id>(len: Option<T>) -> String {
+    match len {
+        Some(n) => n.to_string(),
+        None => String::from("too many to count")
     }
 }
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    if args.len() == 2 {
-        let filename = &args[1];
-        let text = fs::read_to_string(&filename)
-            .expect(&format!("Error reading from {}", filename));
-        let sections: Vec<&str> = text.split("\r\n\r\n").collect();
-        if sections.len() != 2 {
-            panic!()
+    if args.len() == 4 && args[1] == "diff" {
+        let (before, _) = read_inventory::<u128>(&args[2]);
+        let (after, _) = read_inventory::<u128>(&args[3]);
+        let before = IntervalSet::new(&before);
+        let after = IntervalSet::new(&after);
+        for r in before.difference(&after).ranges() {
+            println!("- {}", r);
         }
-        let mut ranges: Vec<Range> = sections[0].lines().map(|s| s.parse().unwrap()).collect();
-        let ingredients: Vec<usize> = sections[1].lines().map(|s| s.parse().unwrap()).collect();
-        let mut count = 0;
-        for i in ingredients {
-            for r in &ranges {
-                if r.contains(i) {
-                    count += 1;
-                    break;
-                }
-            }
+        for r in after.difference(&before).ranges() {
+            println!("+ {}", r);
         }
-        println!("Part1: {}", count);        
-        ranges.sort_by(|a, b| a.start.cmp(&b.start));
-        let mut prev_end = 0;
-        for r in &mut ranges {
-            if r.end <= prev_end {
-                r.start = 1;
-                r.end = 0;
-                continue;
+        println!("Unchanged: {}", total(before.intersection(&after).len()));
+        println!("Changed: {}", total(before.symmetric_difference(&after).len()));
+    } else if (args.len() == 3 || args.len() == 4) && args[1] == "serve" {
+        let (ranges, _) = read_inventory::<u128>(&args[2]);
+        let server = Server::new(ranges);
+        if let Some(socket) = args.get(3) {
+            if let Err(e) = Arc::new(server).serve_socket(socket) {
+                println!("{}", e);
             }
-            if r.start <= prev_end {
-                r.start = prev_end + 1;
+        } else {
+            server.serve_stdin();
+        }
+    } else if args.len() == 3 && args[1] == "report" {
+        let (ranges, ingredients) = read_inventory::<u128>(&args[2]);
+        let index = RangeIndex::new(&ranges);
+        let mut hits = vec![0; ranges.len()];
+        for i in ingredients {
+            let found = index.find(i);
+            println!("{}", range_index::describe(i, &found, &ranges));
+            for r in found {
+                hits[r] += 1;
             }
-            prev_end = r.end;
         }
-        let mut sum = 0;
-        for r in &ranges {
-            sum += r.len()
+        println!("Hits per range:");
+        for (r, h) in hits.iter().enumerate() {
+            println!("line {} ({}): {}", r + 1, ranges[r], h);
         }
-        println!("Part2: {}", sum);
+        let unmatched: Vec<String> = (0..ranges.len()).filter(|r| hits[*r] == 0).map(|r| format!("line {} ({})", r + 1, ranges[r])).collect();
+        if unmatched.is_empty() {
+            println!("Unmatched ranges: none");
+        } else {
+            println!("Unmatched ranges: {}", unmatched.join(", "));
+        }
+    } else if args.len() == 2 {
+        let (ranges, ingredients) = read_inventory::<u128>(&args[1]);
+        let fresh = IntervalSet::new(&ranges);
+        let count = fresh.contains_all(&ingredients).into_iter().filter(|f| *f).count();
+        println!("Part1: {}", count);
+        println!("Part2: {}", total(fresh.len()));
     } else {
         println!("Please provide 1 argument: Filename");
+        println!("Or \"report\", Filename to show which ranges each ingredient is in");
+        println!("Or \"diff\", Filename, Filename to compare fresh ranges");
+        println!("Or \"serve\", Filename, [Socket path] to look up ingredients from stdin (or a Unix socket) one per line");
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,48 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use num_traits::{PrimInt, Unsigned};
+
+/// An unsigned integer type which ingredient IDs can be stored in, up to u128
+pub trait Id: PrimInt + Unsigned + FromStr + Display {}
+
+impl<T: PrimInt + Unsigned + FromStr + Display> Id for T {}
+
+#[derive(Copy, Clone)]
+pub struct Range<T: Id> {
+    pub start: T,
+    pub end: T
+}
+
+impl<T: Id> FromStr for Range<T> {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (start, end) = line.trim().split_once('-').ok_or(format!("Missing '-' in range: {}", line))?;
+        let range = Self {
+            start: start.parse().map_err(|_| format!("Invalid start of range: {}", line))?,
+            end: end.parse().map_err(|_| format!("Invalid end of range: {}", line))?
+        };
+        if range.start > range.end {
+            Err(format!("Range starts after it ends: {}", line))
+        } else {
+            Ok(range)
+        }
+    }
+}
+
+impl<T: Id> Display for Range<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+impl<T: Id> Range<T> {
+    pub fn contains(&self, n: T) -> bool {
+        n >= self.start && n <= self.end
+    }
+
+    /// None if the range covers every value of `T`, so its length doesn't fit in a `T`
+    pub fn len(&self) -> Option<T> {
+        (self.end - self.start).checked_add(&T::one())
+    }
+}
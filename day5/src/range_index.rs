@@ -0,0 +1,72 @@
+use crate::range::{Id, Range};
+
+/// The original (possibly overlapping) ranges, for finding every range which contains a number.
+/// The ranges are sorted by start and treated as an implicit binary tree, where each middle element
+/// also records the furthest end of any range in its subtree, so whole subtrees can be skipped.
+pub struct RangeIndex<T: Id> {
+    /// Each range and its position in the input
+    entries: Vec<(Range<T>, usize)>,
+    max_end: Vec<T>
+}
+
+impl<T: Id> RangeIndex<T> {
+    pub fn new(ranges: &[Range<T>]) -> Self {
+        let mut entries: Vec<(Range<T>, usize)> = ranges.iter().copied().zip(0..).collect();
+        entries.sort_by_key(|(r, _)| r.start);
+        let mut index = Self {
+            max_end: vec![T::zero(); entries.len()],
+            entries
+        };
+        index.build(0, index.entries.len());
+        index
+    }
+
+    fn build(&mut self, lo: usize, hi: usize) -> T {
+        if lo >= hi {
+            return T::zero();
+        }
+        let mid = (lo + hi) / 2;
+        let max = self.entries[mid].0.end
+            .max(self.build(lo, mid))
+            .max(self.build(mid + 1, hi));
+        self.max_end[mid] = max;
+        max
+    }
+
+    /// The positions in the input of every range containing the number, in ascending order
+    pub fn find(&self, n: T) -> Vec<usize> {
+        let mut found = Vec::new();
+        self.search(0, self.entries.len(), n, &mut found);
+        found.sort();
+        found
+    }
+
+    fn search(&self, lo: usize, hi: usize, n: T, found: &mut Vec<usize>) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        if self.max_end[mid] < n {
+            return;
+        }
+        self.search(lo, mid, n, found);
+        let (range, position) = &self.entries[mid];
+        // everything to the right starts at or after this range
+        if range.start <= n {
+            if range.contains(n) {
+                found.push(*position);
+            }
+            self.search(mid + 1, hi, n, found);
+        }
+    }
+}
+
+/// Whether a number was found in any of the ranges, and if so which ones (by line number)
+pub fn describe<T: Id>(n: T, found: &[usize], ranges: &[Range<T>]) -> String {
+    if found.is_empty() {
+        format!("{}: spoiled", n)
+    } else {
+        let lines: Vec<String> = found.iter().map(|r| format!("line {} ({})", r + 1, ranges[*r])).collect();
+        format!("{}: fresh in {}", n, lines.join(", "))
+    }
+}
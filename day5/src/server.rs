@@ -0,0 +1,93 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::fs;
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::process;
+use std::sync::Arc;
+use std::thread;
+use crate::range::Range;
+use crate::range_index::{self, RangeIndex};
+
+/// Answers ingredient lookups one line at a time, against ranges which are only loaded and indexed once
+pub struct Server {
+    ranges: Vec<Range<u128>>,
+    index: RangeIndex<u128>
+}
+
+impl Server {
+    pub fn new(ranges: Vec<Range<u128>>) -> Self {
+        Self {
+            index: RangeIndex::new(&ranges),
+            ranges
+        }
+    }
+
+    fn answer(&self, line: &str) -> String {
+        match line.trim().parse() {
+            Ok(n) => range_index::describe(n, &self.index.find(n), &self.ranges),
+            Err(_) => format!("Invalid ingredient: {}", line.trim())
+        }
+    }
+
+    /// Answers each line of stdin until it is closed
+    pub fn serve_stdin(&self) {
+        for line in io::stdin().lock().lines() {
+            let line = line.expect("Error reading from stdin");
+            if !line.trim().is_empty() {
+                println!("{}", self.answer(&line));
+            }
+        }
+    }
+
+    /// Listens on a Unix socket, answering each connection on its own thread until it is closed.
+    /// The socket is removed when the server is stopped with Ctrl-C or terminated.
+    pub fn serve_socket(self: Arc<Self>, path: &str) -> Result<(), String> {
+        remove_stale_socket(path)?;
+        let listener = UnixListener::bind(path)
+            .map_err(|e| format!("Error listening on {}: {}", path, e))?;
+        let socket = path.to_string();
+        ctrlc::set_handler(move || {
+            let _ = fs::remove_file(&socket);
+            process::exit(0);
+        }).map_err(|e| format!("Error handling Ctrl-C: {}", e))?;
+        println!("Listening on {}", path);
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(s) => s,
+                Err(e) => {
+                    println!("Error accepting connection: {}", e);
+                    continue;
+                }
+            };
+            let server = self.clone();
+            thread::spawn(move || {
+                let mut writer = &stream;
+                for line in BufReader::new(&stream).lines() {
+                    let Ok(line) = line else {
+                        break;
+                    };
+                    if !line.trim().is_empty() && writeln!(writer, "{}", server.answer(&line)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Removes a socket left behind by a server which didn't shut down cleanly,
+/// but refuses to replace anything which isn't a socket or still has a server listening on it
+fn remove_stale_socket(path: &str) -> Result<(), String> {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return Ok(());
+    };
+    if !metadata.file_type().is_socket() {
+        Err(format!("{} already exists and is not a socket", path))
+    } else if UnixStream::connect(Path::new(path)).is_ok() {
+        Err(format!("Another server is already listening on {}", path))
+    } else {
+        fs::remove_file(path).map_err(|e| format!("Error removing stale socket {}: {}", path, e))
+    }
+}
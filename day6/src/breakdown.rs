@@ -0,0 +1,99 @@
+use crate::operator::Value;
+use crate::reading::Reading;
+use crate::worksheet::Worksheet;
+
+/// How one problem was read and answered in one reading
+pub struct Line {
+    pub problem: usize,
+    pub columns: (usize, usize),
+    pub operator: char,
+    pub reading: Reading,
+    pub operands: Vec<usize>,
+    /// The answer, or why it couldn't be read or calculated
+    pub result: Result<String, String>
+}
+
+/// Every problem in every reading, ordered by problem then reading
+pub struct Breakdown {
+    pub lines: Vec<Line>
+}
+
+impl Breakdown {
+    pub fn new<V: Value>(worksheet: &Worksheet, readings: &[Reading]) -> Self {
+        let mut lines = Vec::new();
+        for (i, problem) in worksheet.problems.iter().enumerate() {
+            for reading in readings {
+                let (operands, result) = match problem.numbers(reading) {
+                    Ok(numbers) => {
+                        let result = problem.operator.calculate::<V>(&numbers).map(|v| v.to_string());
+                        (numbers, result)
+                    },
+                    Err(e) => (Vec::new(), Err(e))
+                };
+                lines.push(Line {
+                    problem: i + 1,
+                    columns: problem.columns,
+                    operator: problem.operator.symbol(),
+                    reading: *reading,
+                    operands,
+                    result
+                });
+            }
+        }
+        Self {
+            lines
+        }
+    }
+
+    fn cells(&self) -> Vec<[String; 6]> {
+        let mut v = vec![["problem", "columns", "operator", "reading", "operands", "result"].map(String::from)];
+        for line in &self.lines {
+            let operands: Vec<String> = line.operands.iter().map(|n| n.to_string()).collect();
+            v.push([
+                line.problem.to_string(),
+                format!("{}-{}", line.columns.0, line.columns.1),
+                line.operator.to_string(),
+                line.reading.to_string(),
+                operands.join(" "),
+                match &line.result {
+                    Ok(answer) => answer.clone(),
+                    Err(e) => format!("error: {}", e)
+                }
+            ]);
+        }
+        v
+    }
+
+    /// One line per problem and reading, with each column padded to line up
+    pub fn table(&self) -> String {
+        let cells = self.cells();
+        let mut widths = [0; 6];
+        for row in &cells {
+            for (w, cell) in widths.iter_mut().zip(row) {
+                *w = (*w).max(cell.len());
+            }
+        }
+        let mut s = String::new();
+        for row in &cells {
+            let padded: Vec<String> = row.iter().zip(widths).map(|(cell, w)| format!("{:<w$}", cell, w = w)).collect();
+            s.push_str(padded.join("  ").trim_end());
+            s.push('\n');
+        }
+        s
+    }
+
+    /// One line per problem and reading, with operands separated by spaces and errors quoted
+    pub fn csv(&self) -> String {
+        let mut s = String::new();
+        for row in self.cells() {
+            let quoted: Vec<String> = row.iter().map(|cell| if cell.contains(',') || cell.contains('"') {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.clone()
+            }).collect();
+            s.push_str(&quoted.join(","));
+            s.push('\n');
+        }
+        s
+    }
+}
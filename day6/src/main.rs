@@ -1,128 +1,97 @@
 use std::fs;
 use std::env;
+use num_bigint::BigInt;
+use crate::breakdown::Breakdown;
+use crate::operator::Value;
+use crate::reading::Reading;
+use crate::worksheet::Worksheet;
 
-enum Operator {
-    Add,
-    Multiply
-}
+mod breakdown;
+mod operator;
+mod reading;
+mod worksheet;
 
-impl Operator {
-    fn calculate(&self, inputs: &Vec<Vec<usize>>, i: usize) -> usize {
-        let mut n = match self {
-            Self::Add => 0,
-            Self::Multiply => 1
-        };
-        for input in inputs {
-            n = match self {
-                Self::Add => n + input[i],
-                Self::Multiply => n * input[i]
-            }
-        }
-        n
-    }
-
-    fn calculate_right_to_left(&self, inputs: &Vec<Vec<String>>, i: usize) -> usize {
-        let data: Vec<&String> = inputs.iter().map(|v| &v[i]).collect();
-        let mut numbers: Vec<usize> = Vec::new();
-        for i in 0..data[0].len() {
-            let mut s = String::new();
-            for d in &data {
-                let ch = d.chars().skip(i).next().unwrap();
-                if ch != ' ' {
-                    s.push(ch);
-                }
-            }
-            if s.len() != 0 {
-                numbers.push(s.parse().unwrap());
-            }
-        }
-
-        let mut n = match self {
-            Self::Add => 0,
-            Self::Multiply => 1
-        };
-        for number in numbers {
-            n = match self {
-                Self::Add => n + number,
-                Self::Multiply => n * number
-            }
-        }
-        n
+/// The sum of every problem's answer, or the first problem which couldn't be read or calculated
+fn total<V: Value>(worksheet: &Worksheet, reading: &Reading) -> Result<BigInt, String> {
+    let mut sum = BigInt::ZERO;
+    for (i, problem) in worksheet.problems.iter().enumerate() {
+        let answer: V = problem.operator.calculate(&problem.numbers(reading)?).map_err(|e| format!("Problem {}: {}", i + 1, e))?;
+        sum += answer.into_big();
     }
+    Ok(sum)
 }
 
-fn parse_operators(line: &str) -> Vec<(Operator, usize)> {
-    let mut v = Vec::new();
-    let mut spaces = 0;
-    let mut last_operator = None;
-    for ch in line.chars() {
-        if ch == '+' {
-            if let Some(op) = last_operator {
-                v.push((op, spaces));
-            }
-            last_operator = Some(Operator::Add);
-            spaces = 0;
-        } else if ch == '*' {
-            if let Some(op) = last_operator {
-                v.push((op, spaces));
-            }
-            last_operator = Some(Operator::Multiply);
-            spaces = 0;
+/// Prints the total for each reading on its own line, lined up, with the puzzle's parts labelled
+fn report<V: Value>(worksheet: &Worksheet, readings: &[Reading]) {
+    let width = readings.iter().map(|r| r.to_string().len()).max().unwrap_or(0);
+    for reading in readings {
+        let label = if *reading == Reading::PART1 {
+            " (Part1)"
+        } else if *reading == Reading::PART2 {
+            " (Part2)"
         } else {
-            spaces += 1;
-        }
-    }
-    if let Some(op) = last_operator {
-        v.push((op, spaces + 1));
-    }
-    v
-}
-
-fn parse_numbers(line: &str) -> Vec<usize> {
-    let mut v = Vec::new();
-    for mut s in line.split(' ') {
-        s = s.trim();
-        if s.len() != 0 {
-            v.push(s.parse().unwrap())
-        }
+            ""
+        };
+        let result = match total::<V>(worksheet, reading) {
+            Ok(sum) => sum.to_string(),
+            Err(e) => e
+        };
+        println!("{:<width$}  {}{}", reading.to_string(), result, label, width = width);
     }
-    v
 }
 
-fn parse_strings(line: &str, lengths: &Vec<usize>) -> Vec<String> {
-    let mut v = Vec::new();
-    let mut sum = 0;
-    for l in lengths {
-        v.push(line.chars().skip(sum).take(*l).collect::<String>());
-        sum += *l + 1;
+fn parse_readings(text: &str) -> Result<Vec<Reading>, String> {
+    match text {
+        "all" => Ok(Reading::all()),
+        "parts" => Ok(vec![Reading::PART1, Reading::PART2]),
+        _ => text.split(',').map(|r| r.parse()).collect()
     }
-    v
 }
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    if args.len() == 2 {
-        let filename = &args[1];
-        let text = fs::read_to_string(&filename)
-            .expect(&format!("Error reading from {}", filename));
-        // part1
-        let count = text.lines().count();
-        let numbers: Vec<Vec<usize>> = text.lines().take(count - 1).map(parse_numbers).collect();
-        let operators: Vec<Operator> = parse_operators(text.lines().skip(count - 1).next().unwrap()).into_iter().map(|(o, _)| o).collect();
-        let mut sum = 0;
-        for i in 0..operators.len() {
-            sum += operators[i].calculate(&numbers, i);
-        }
-        println!("Part1: {}", sum);
-        // part2
-        let lengths: Vec<usize> = parse_operators(text.lines().skip(count - 1).next().unwrap()).into_iter().map(|(_, l)| l).collect();
-        let strings: Vec<Vec<String>> = text.lines().take(count - 1).map(|line| parse_strings(line, &lengths)).collect();
-        sum = 0;
-        for i in 0..operators.len() {
-            sum += operators[i].calculate_right_to_left(&strings, i);
+    let (output, mut rest) = match args.get(1).map(|a| a.as_str()) {
+        Some("table") | Some("csv") => (Some(args[1].as_str()), &args[2..]),
+        _ => (None, &args[1..])
+    };
+    let big = rest.len() > 1 && rest[rest.len() - 1] == "big";
+    if big {
+        rest = &rest[..(rest.len() - 1)];
+    }
+    if rest.len() == 1 || rest.len() == 2 {
+        let filename = &rest[0];
+        let readings = match parse_readings(rest.get(1).map(|s| s.as_str()).unwrap_or("all")) {
+            Ok(r) => r,
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        };
+        let text = fs::read_to_string(filename)
+            .unwrap_or_else(|_| panic!("Error reading from {}", filename));
+        let worksheet: Worksheet = match text.parse() {
+            Ok(w) => w,
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        };
+        let breakdown = || if big {
+            Breakdown::new::<BigInt>(&worksheet, &readings)
+        } else {
+            Breakdown::new::<usize>(&worksheet, &readings)
+        };
+        match output {
+            Some("table") => print!("{}", breakdown().table()),
+            Some("csv") => print!("{}", breakdown().csv()),
+            _ => if big {
+                report::<BigInt>(&worksheet, &readings);
+            } else {
+                report::<usize>(&worksheet, &readings);
+            }
         }
-        println!("Part2: {}", sum);
     } else {
-        println!("Please provide 1 argument: Filename");
+        println!("Please provide 1-2 arguments: Filename [Readings] (Readings is \"all\", \"parts\" or a comma separated list like rows-down-right,columns-up-left, and add \"big\" for arbitrary precision)");
+        println!("Optionally preceded by \"table\" or \"csv\" to show the operands and result of every problem");
     }
-}
\ No newline at end of file
+}
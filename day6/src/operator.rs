@@ -0,0 +1,153 @@
+use std::fmt::Display;
+use num_bigint::BigInt;
+
+/// A type which answers can be calculated in, failing rather than giving a wrong answer
+pub trait Value: Sized + Display {
+    fn from_usize(n: usize) -> Self;
+    fn into_big(self) -> BigInt;
+    fn combine(operator: &Operator, a: Self, b: Self) -> Result<Self, String>;
+}
+
+/// Checked arithmetic, which fails on overflow or a negative result
+impl Value for usize {
+    fn from_usize(n: usize) -> Self {
+        n
+    }
+
+    fn into_big(self) -> BigInt {
+        BigInt::from(self)
+    }
+
+    fn combine(operator: &Operator, a: Self, b: Self) -> Result<Self, String> {
+        let result = match operator {
+            Operator::Add => a.checked_add(b),
+            Operator::Multiply => a.checked_mul(b),
+            Operator::Subtract => a.checked_sub(b),
+            Operator::Divide => a.checked_div(b),
+            Operator::Min => Some(a.min(b)),
+            Operator::Max => Some(a.max(b)),
+            Operator::Concatenate => 10_usize.checked_pow(b.checked_ilog10().unwrap_or(0) + 1)
+                .and_then(|shift| a.checked_mul(shift))
+                .and_then(|n| n.checked_add(b)),
+            Operator::Power => u32::try_from(b).ok().and_then(|exp| a.checked_pow(exp))
+        };
+        result.ok_or(format!("{} {} {} is out of range", a, operator.symbol(), b))
+    }
+}
+
+/// The most bits a power may need before it is considered impossibly large, about 300,000 decimal digits
+const MAX_POWER_BITS: u64 = 1 << 20;
+
+/// Arbitrary precision arithmetic, which only fails on division by zero or an impossibly large power
+impl Value for BigInt {
+    fn from_usize(n: usize) -> Self {
+        BigInt::from(n)
+    }
+
+    fn into_big(self) -> BigInt {
+        self
+    }
+
+    fn combine(operator: &Operator, a: Self, b: Self) -> Result<Self, String> {
+        Ok(match operator {
+            Operator::Add => a + b,
+            Operator::Multiply => a * b,
+            Operator::Subtract => a - b,
+            Operator::Divide if b == BigInt::ZERO => return Err(format!("{} / 0 is undefined", a)),
+            Operator::Divide => a / b,
+            Operator::Min => a.min(b),
+            Operator::Max => a.max(b),
+            Operator::Concatenate => format!("{}{}", a, b).parse().unwrap(),
+            Operator::Power => match u32::try_from(&b) {
+                Ok(exp) if a.bits() <= 1 || a.bits().saturating_mul(exp.into()) <= MAX_POWER_BITS => a.pow(exp),
+                _ => return Err(format!("{} ^ {} is too large", abbreviate(&a), abbreviate(&b)))
+            }
+        })
+    }
+}
+
+/// Numbers too long to read in an error are shown by their number of digits instead
+fn abbreviate(n: &BigInt) -> String {
+    let s = n.to_string();
+    if s.len() > 40 {
+        format!("({} digit number)", s.trim_start_matches('-').len())
+    } else {
+        s
+    }
+}
+
+/// An operation which combines a problem's numbers into its answer.
+/// Numbers are combined in the order they are read, as `((a op b) op c) op ...`,
+/// so the order only matters for the operators which aren't commutative (`-`, `/`, `|` and `^`).
+#[derive(Copy, Clone)]
+pub enum Operator {
+    /// `+`, with identity 0
+    Add,
+    /// `*`, with identity 1
+    Multiply,
+    /// `-`, taking each later number away from the first
+    Subtract,
+    /// `/`, integer division of the first number by each later number in turn
+    Divide,
+    /// `<`, the smallest number, with no identity since there is no largest number
+    Min,
+    /// `>`, the largest number, with identity 0
+    Max,
+    /// `|`, the digits of each number written one after another
+    Concatenate,
+    /// `^`, the first number raised to the power of each later number in turn
+    Power
+}
+
+impl Operator {
+    pub fn from_symbol(ch: char) -> Option<Self> {
+        match ch {
+            '+' => Some(Self::Add),
+            '*' => Some(Self::Multiply),
+            '-' => Some(Self::Subtract),
+            '/' => Some(Self::Divide),
+            '<' => Some(Self::Min),
+            '>' => Some(Self::Max),
+            '|' => Some(Self::Concatenate),
+            '^' => Some(Self::Power),
+            _ => None
+        }
+    }
+
+    pub fn symbol(&self) -> char {
+        match self {
+            Self::Add => '+',
+            Self::Multiply => '*',
+            Self::Subtract => '-',
+            Self::Divide => '/',
+            Self::Min => '<',
+            Self::Max => '>',
+            Self::Concatenate => '|',
+            Self::Power => '^'
+        }
+    }
+
+    /// The answer when there are no numbers, for operators where that makes sense
+    pub fn identity(&self) -> Option<usize> {
+        match self {
+            Self::Add => Some(0),
+            Self::Multiply => Some(1),
+            Self::Max => Some(0),
+            Self::Min | Self::Subtract | Self::Divide | Self::Concatenate | Self::Power => None
+        }
+    }
+
+    /// Folds the numbers together from the first, or returns the identity if there are none
+    pub fn calculate<V: Value>(&self, numbers: &[usize]) -> Result<V, String> {
+        match numbers.split_first() {
+            Some((first, rest)) => {
+                let mut n = V::from_usize(*first);
+                for number in rest {
+                    n = V::combine(self, n, V::from_usize(*number))?;
+                }
+                Ok(n)
+            },
+            None => self.identity().map(V::from_usize).ok_or(format!("No numbers to {}", self.symbol()))
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Whether each number is written along a line or down a column of the problem
+#[derive(Copy, Clone, PartialEq)]
+pub enum Direction {
+    Rows,
+    Columns
+}
+
+/// A way of reading the numbers out of a problem's grid of digits.
+/// The direction decides what makes up a number, `down` and `right` decide which end of the grid is read first:
+/// for rows, `down` orders the numbers and `right` orders each number's digits,
+/// for columns, `right` orders the numbers and `down` orders each number's digits.
+#[derive(Copy, Clone, PartialEq)]
+pub struct Reading {
+    pub direction: Direction,
+    pub down: bool,
+    pub right: bool
+}
+
+impl Reading {
+    /// The way part 1 reads a problem
+    pub const PART1: Reading = Reading { direction: Direction::Rows, down: true, right: true };
+    /// The way part 2 reads a problem
+    pub const PART2: Reading = Reading { direction: Direction::Columns, down: true, right: true };
+
+    /// Every reading, starting with those of part 1 and part 2
+    pub fn all() -> Vec<Reading> {
+        let mut v = Vec::new();
+        for direction in [Direction::Rows, Direction::Columns] {
+            for (down, right) in [(true, true), (true, false), (false, true), (false, false)] {
+                v.push(Reading { direction, down, right });
+            }
+        }
+        v
+    }
+}
+
+impl FromStr for Reading {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = text.split('-').collect();
+        if parts.len() != 3 {
+            return Err(format!("Reading should look like rows-down-right or columns-up-left: {}", text));
+        }
+        Ok(Self {
+            direction: match parts[0] {
+                "rows" => Direction::Rows,
+                "columns" => Direction::Columns,
+                _ => return Err(format!("Expected rows or columns: {}", parts[0]))
+            },
+            down: match parts[1] {
+                "down" => true,
+                "up" => false,
+                _ => return Err(format!("Expected down or up: {}", parts[1]))
+            },
+            right: match parts[2] {
+                "right" => true,
+                "left" => false,
+                _ => return Err(format!("Expected right or left: {}", parts[2]))
+            }
+        })
+    }
+}
+
+impl Display for Reading {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let direction = match self.direction {
+            Direction::Rows => "rows",
+            Direction::Columns => "columns"
+        };
+        write!(f, "{}-{}-{}", direction, if self.down { "down" } else { "up" }, if self.right { "right" } else { "left" })
+    }
+}
@@ -0,0 +1,131 @@
+use std::str::FromStr;
+use crate::operator::Operator;
+use crate::reading::{Direction, Reading};
+
+const TAB_WIDTH: usize = 8;
+
+/// One problem on the worksheet: its operator and the block of characters above it
+pub struct Problem {
+    pub operator: Operator,
+    /// The first and last character column of the problem (counting from 1)
+    pub columns: (usize, usize),
+    /// The problem's characters from each number line, padded with spaces to the same width
+    pub rows: Vec<Vec<char>>
+}
+
+/// Problems are separated by columns which are blank on every line, including the operator line at the bottom
+pub struct Worksheet {
+    pub problems: Vec<Problem>
+}
+
+impl FromStr for Worksheet {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut lines: Vec<Vec<char>> = text.lines().map(expand_tabs).collect();
+        while lines.last().is_some_and(|l| l.iter().all(|c| *c == ' ')) {
+            lines.pop();
+        }
+        if lines.len() < 2 {
+            return Err("Expected at least one line of numbers and a line of operators".to_string());
+        }
+        // short lines are treated as if they had trailing spaces
+        let width = lines.iter().map(|l| l.len()).max().unwrap();
+        for line in &mut lines {
+            line.resize(width, ' ');
+        }
+        let operator_line = lines.pop().unwrap();
+        let mut errors = Vec::new();
+        for (y, line) in lines.iter().enumerate() {
+            for (x, ch) in line.iter().enumerate() {
+                if *ch != ' ' && !ch.is_ascii_digit() {
+                    errors.push(format!("Line {} column {}: unexpected '{}' in a line of numbers", y + 1, x + 1, ch));
+                }
+            }
+        }
+        let mut problems = Vec::new();
+        let mut x = 0;
+        while x < width {
+            let blank = |x: usize| operator_line[x] == ' ' && lines.iter().all(|l| l[x] == ' ');
+            if blank(x) {
+                x += 1;
+                continue;
+            }
+            let start = x;
+            while x < width && !blank(x) {
+                x += 1;
+            }
+            let columns = (start + 1, x);
+            let symbols: Vec<char> = operator_line[start..x].iter().filter(|c| **c != ' ').copied().collect();
+            match symbols[..] {
+                [symbol] => match Operator::from_symbol(symbol) {
+                    Some(operator) => problems.push(Problem {
+                        operator,
+                        columns,
+                        rows: lines.iter().map(|l| l[start..x].to_vec()).collect()
+                    }),
+                    None => errors.push(format!("Columns {}-{}: unknown operator '{}'", columns.0, columns.1, symbol))
+                },
+                [] => errors.push(format!("Columns {}-{}: no operator below this problem", columns.0, columns.1)),
+                _ => errors.push(format!("Columns {}-{}: more than one operator ({}) below this problem, are the columns misaligned?",
+                    columns.0, columns.1, symbols.iter().collect::<String>()))
+            }
+        }
+        for p in &problems {
+            for (y, row) in p.rows.iter().enumerate() {
+                let s: String = row.iter().collect();
+                if s.split_whitespace().count() > 1 {
+                    errors.push(format!("Line {} columns {}-{}: more than one number in this problem, are the columns misaligned?",
+                        y + 1, p.columns.0, p.columns.1));
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(Self {
+                problems
+            })
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+}
+
+/// Replaces tabs with spaces up to the next tab stop, so columns line up as they would in an editor
+fn expand_tabs(line: &str) -> Vec<char> {
+    let mut v = Vec::new();
+    for ch in line.chars() {
+        if ch == '\t' {
+            v.push(' ');
+            while !v.len().is_multiple_of(TAB_WIDTH) {
+                v.push(' ');
+            }
+        } else if ch != '\r' {
+            v.push(ch);
+        }
+    }
+    v
+}
+
+impl Problem {
+    /// The numbers of this problem, in the order the reading finds them
+    pub fn numbers(&self, reading: &Reading) -> Result<Vec<usize>, String> {
+        let width = self.columns.1 + 1 - self.columns.0;
+        let mut ys: Vec<usize> = (0..self.rows.len()).collect();
+        let mut xs: Vec<usize> = (0..width).collect();
+        if !reading.down {
+            ys.reverse();
+        }
+        if !reading.right {
+            xs.reverse();
+        }
+        let lines: Vec<Vec<char>> = match reading.direction {
+            Direction::Rows => ys.iter().map(|y| xs.iter().map(|x| self.rows[*y][*x]).collect()).collect(),
+            Direction::Columns => xs.iter().map(|x| ys.iter().map(|y| self.rows[*y][*x]).collect()).collect()
+        };
+        lines.into_iter()
+            .map(|line| line.into_iter().filter(|c| *c != ' ').collect::<String>())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().map_err(|_| format!("Columns {}-{}: {} is too large", self.columns.0, self.columns.1, s)))
+            .collect()
+    }
+}